@@ -0,0 +1,106 @@
+use segment::InvalidSegmentError;
+
+// One component's entry within a frame header's component list.
+#[derive(Debug, PartialEq)]
+pub struct FrameComponent {
+    pub id : u8,
+    pub horizontal_sampling_factor : u8,
+    pub vertical_sampling_factor : u8,
+    pub quantization_table_selector : u8,
+}
+
+// Parsed from a StartOfFrame (SOFn) segment body: the image's sample
+// precision, dimensions, and per-component layout.
+#[derive(Debug, PartialEq)]
+pub struct FrameHeader {
+    pub precision : u8,
+    pub height : u16,
+    pub width : u16,
+    pub components : Vec<FrameComponent>,
+}
+
+pub fn parse_frame_header(body : &[u8]) -> Result<FrameHeader, InvalidSegmentError> {
+    if body.len() < 6 {
+        return Err(InvalidSegmentError::malformed_body(format!("SOF body is only {} bytes, but needs at least 6 before the component list.", body.len())));
+    }
+
+    let precision = body[0];
+    let height = (body[1] as u16) * 256 + (body[2] as u16);
+    let width = (body[3] as u16) * 256 + (body[4] as u16);
+    let component_count = body[5] as usize;
+
+    let expected_len = 6 + component_count * 3;
+    if body.len() < expected_len {
+        return Err(InvalidSegmentError::malformed_body(format!("SOF claims {} components, needing {} bytes, but body is only {} bytes.", component_count, expected_len, body.len())));
+    }
+
+    let mut components = Vec::with_capacity(component_count);
+    for i in 0..component_count {
+        let base = 6 + i * 3;
+        let sampling_factors = body[base + 1];
+        components.push(FrameComponent {
+            id : body[base],
+            horizontal_sampling_factor : sampling_factors >> 4,
+            vertical_sampling_factor : sampling_factors & 0x0f,
+            quantization_table_selector : body[base + 2],
+        });
+    }
+
+    Ok(FrameHeader { precision : precision, height : height, width : width, components : components })
+}
+
+// Parsed from a DefineRestartInterval (DRI) segment body: the number of
+// MCUs between consecutive restart markers in the scan data.
+pub fn parse_restart_interval(body : &[u8]) -> Result<u16, InvalidSegmentError> {
+    if body.len() < 2 {
+        return Err(InvalidSegmentError::malformed_body(format!("DRI body is only {} bytes, but needs 2.", body.len())));
+    }
+    Ok((body[0] as u16) * 256 + (body[1] as u16))
+}
+
+#[cfg(test)]
+mod frame_tests {
+    use super::*;
+
+    #[test]
+    fn parses_frame_header_with_components() {
+        let body = vec![0x08u8,            // precision
+                         0x01u8, 0x00u8,    // height = 256
+                         0x00u8, 0x80u8,    // width = 128
+                         0x02u8,            // component count
+                         0x01u8, 0x22u8, 0x00u8,  // id 1, H=2 V=2, quant table 0
+                         0x02u8, 0x11u8, 0x01u8]; // id 2, H=1 V=1, quant table 1
+        let header = parse_frame_header(&body).unwrap();
+
+        assert_eq!(header.precision, 8);
+        assert_eq!(header.height, 256);
+        assert_eq!(header.width, 128);
+        assert_eq!(header.components.len(), 2);
+        assert_eq!(header.components[0], FrameComponent { id : 1, horizontal_sampling_factor : 2, vertical_sampling_factor : 2, quantization_table_selector : 0 });
+        assert_eq!(header.components[1], FrameComponent { id : 2, horizontal_sampling_factor : 1, vertical_sampling_factor : 1, quantization_table_selector : 1 });
+    }
+
+    #[test]
+    fn rejects_truncated_component_list() {
+        let body = vec![0x08u8, 0x01u8, 0x00u8, 0x00u8, 0x80u8, 0x02u8, 0x01u8, 0x22u8, 0x00u8];
+        let result = parse_frame_header(&body);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parses_restart_interval() {
+        let body = vec![0x00u8, 0x20u8];
+        let interval = parse_restart_interval(&body).unwrap();
+
+        assert_eq!(interval, 32);
+    }
+
+    #[test]
+    fn rejects_truncated_restart_interval() {
+        let body = vec![0x00u8];
+        let result = parse_restart_interval(&body);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use segment::InvalidSegmentError;
+
+#[derive(Debug, PartialEq)]
+pub enum TableClass {
+    Dc,
+    Ac,
+}
+
+// A canonical Huffman table decoded from a DefineHuffmanTable segment body,
+// per JPEG Annex C. `codes` maps (code length in bits, code value) to the
+// symbol it decodes to; `min_code`/`max_code` are indexed by code length
+// (1..=16, index 0 unused) and let a decoder quickly tell whether a given
+// length has any codes at all before doing the full lookup.
+#[derive(Debug, PartialEq)]
+pub struct HuffmanTable {
+    pub class : TableClass,
+    pub destination_id : u8,
+    codes : HashMap<(u8, u16), u8>,
+    min_code : [Option<u16>; 17],
+    max_code : [Option<u16>; 17],
+}
+
+impl HuffmanTable {
+    pub fn lookup(&self, length : u8, code : u16) -> Option<u8> {
+        self.codes.get(&(length, code)).map(|&symbol| symbol)
+    }
+
+    pub fn min_code_of_length(&self, length : u8) -> Option<u16> {
+        self.min_code[length as usize]
+    }
+
+    pub fn max_code_of_length(&self, length : u8) -> Option<u16> {
+        self.max_code[length as usize]
+    }
+}
+
+// A DHT body may pack several tables back-to-back, so this parses until
+// the body is exhausted.
+pub fn parse_tables(body : &[u8]) -> Result<Vec<HuffmanTable>, InvalidSegmentError> {
+    let mut tables = Vec::<HuffmanTable>::new();
+    let mut offset = 0;
+    while offset < body.len() {
+        let (table, consumed) = try!(parse_one_table(&body[offset..]));
+        tables.push(table);
+        offset = offset + consumed;
+    }
+    Ok(tables)
+}
+
+fn parse_one_table(body : &[u8]) -> Result<(HuffmanTable, usize), InvalidSegmentError> {
+    if body.len() < 17 {
+        return Err(InvalidSegmentError::malformed_body(String::from("DHT table is missing its class/id byte or its 16 BITS counts.")));
+    }
+
+    let class_and_id = body[0];
+    let class = match class_and_id >> 4 {
+        0 => TableClass::Dc,
+        1 => TableClass::Ac,
+        other => return Err(InvalidSegmentError::malformed_body(format!("DHT table class nibble {} is neither 0 (DC) nor 1 (AC).", other))),
+    };
+    let destination_id = class_and_id & 0x0f;
+    if destination_id > 3 {
+        return Err(InvalidSegmentError::malformed_body(format!("DHT destination id {} is out of the valid 0-3 range.", destination_id)));
+    }
+
+    let bits = &body[1..17];
+    let total_codes : usize = bits.iter().map(|&count| count as usize).sum();
+    if body.len() < 17 + total_codes {
+        return Err(InvalidSegmentError::malformed_body(format!("DHT table claims {} HUFFVAL bytes but only {} remain.", total_codes, body.len() - 17)));
+    }
+    let huffval = &body[17 .. 17 + total_codes];
+
+    let mut codes = HashMap::new();
+    let mut min_code = [None; 17];
+    let mut max_code = [None; 17];
+    let mut code : u32 = 0;
+    let mut next_symbol = 0;
+    for length in 1..17usize {
+        let count = bits[length - 1] as usize;
+        if count > 0 {
+            min_code[length] = Some(code as u16);
+        }
+        for _ in 0..count {
+            if code >= (1u32 << length) {
+                return Err(InvalidSegmentError::malformed_body(format!("DHT table has more length-{} codes than are representable.", length)));
+            }
+            codes.insert((length as u8, code as u16), huffval[next_symbol]);
+            next_symbol = next_symbol + 1;
+            code = code + 1;
+        }
+        if count > 0 {
+            max_code[length] = Some((code - 1) as u16);
+        }
+        code = code << 1;
+    }
+
+    let table = HuffmanTable { class : class, destination_id : destination_id, codes : codes, min_code : min_code, max_code : max_code };
+    Ok((table, 17 + total_codes))
+}
+
+#[cfg(test)]
+mod huffman_tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_dc_table() {
+        // Class 0 (DC), destination 0. Two length-2 codes and one length-3 code.
+        let body = vec![0x00u8,
+                         0x00u8, 0x02u8, 0x01u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8,
+                         0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8,
+                         0x05u8, 0x07u8, 0x09u8];
+        let tables = parse_tables(&body).unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].class, TableClass::Dc);
+        assert_eq!(tables[0].destination_id, 0);
+        // Canonical codes: length 2 -> 00, 01; length 3 -> 100
+        assert_eq!(tables[0].lookup(2, 0b00), Some(0x05u8));
+        assert_eq!(tables[0].lookup(2, 0b01), Some(0x07u8));
+        assert_eq!(tables[0].lookup(3, 0b100), Some(0x09u8));
+        assert_eq!(tables[0].min_code_of_length(2), Some(0));
+        assert_eq!(tables[0].max_code_of_length(2), Some(1));
+    }
+
+    #[test]
+    fn rejects_truncated_huffval() {
+        let body = vec![0x10u8,
+                         0x00u8, 0x01u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8,
+                         0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8];
+        let result = parse_tables(&body);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_codes() {
+        // Class 1 (AC). Claims three length-1 codes, but only two (0, 1) are representable.
+        let body = vec![0x10u8,
+                         0x03u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8,
+                         0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8, 0x00u8,
+                         0x01u8, 0x02u8, 0x03u8];
+        let result = parse_tables(&body);
+
+        assert!(result.is_err());
+    }
+}
@@ -1,10 +1,13 @@
 #![feature(box_syntax)]
-#![feature(slice_patterns)]
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
 mod marker;
 mod segment;
+mod huffman;
+mod quantization;
+mod frame;
+mod reader;
 
 use std::fs::File;
 use std::io::prelude::*;
@@ -120,5 +123,45 @@ mod tests {
         } else {
             panic!("Valid marker returned an error.");
         }
+
+        let bytes5 = [0xffu8, 0xd3u8];
+        let result5 = Marker::from_bytes(&bytes5);
+        if let Ok(marker) = result5 {
+            assert_eq!(marker, Marker::RestartMarker(3));
+        } else {
+            panic!("Valid restart marker returned an error.");
+        }
+
+        let bytes6 = [0xffu8, 0xe1u8];
+        let result6 = Marker::from_bytes(&bytes6);
+        if let Ok(marker) = result6 {
+            assert_eq!(marker, Marker::Application(1));
+        } else {
+            panic!("Valid APPn marker returned an error.");
+        }
+
+        let bytes7 = [0xffu8, 0xc2u8];
+        let result7 = Marker::from_bytes(&bytes7);
+        if let Ok(marker) = result7 {
+            assert_eq!(marker, Marker::StartOfFrame(FrameType::Progressive));
+        } else {
+            panic!("Valid progressive SOF marker returned an error.");
+        }
+
+        let bytes8 = [0xffu8, 0xc0u8];
+        let result8 = Marker::from_bytes(&bytes8);
+        if let Ok(marker) = result8 {
+            assert_eq!(marker, Marker::StartOfFrame(FrameType::Baseline));
+        } else {
+            panic!("Valid baseline SOF marker returned an error.");
+        }
+
+        let bytes9 = [0xffu8, 0xddu8];
+        let result9 = Marker::from_bytes(&bytes9);
+        if let Ok(marker) = result9 {
+            assert_eq!(marker, Marker::DefineRestartInterval);
+        } else {
+            panic!("Valid DRI marker returned an error.");
+        }
     }
 }
@@ -1,5 +1,21 @@
 use std::fmt;
 use std::error;
+use reader::{Reader, SliceReader};
+
+// Which kind of frame a StartOfFrame marker introduces. JPEG numbers these
+// SOF0-SOF15, skipping 0xC4 (DHT), 0xC8 (reserved), and 0xCC (DAC), and
+// splits them into a Huffman-coded half (SOF0-SOF7) and an arithmetic-coded
+// half (SOF9-SOF15) that otherwise mirror each other's frame types.
+#[derive(Debug, PartialEq)]
+pub enum FrameType {
+    Baseline,
+    ExtendedSequential,
+    Progressive,
+    Lossless,
+    DifferentialSequential,
+    DifferentialProgressive,
+    DifferentialLossless,
+}
 
 #[derive(Debug, PartialEq)]
 pub enum Marker {
@@ -9,22 +25,56 @@ pub enum Marker {
     StartOfScan,
     DefineQuantizationTable,
     Comment,
+    // RST0-RST7 (0xFFD0-0xFFD7): restart markers. These appear periodically
+    // inside the entropy-coded data that follows StartOfScan, resetting the
+    // Huffman decoder's predictor state.
+    RestartMarker(u8),
+    // APP0-APP15 (0xFFE0-0xFFEF): application-specific segments (JFIF, Exif, ...).
+    Application(u8),
+    // SOF0-SOF15, excluding the markers that are reused for other purposes.
+    StartOfFrame(FrameType),
+    DefineRestartInterval,
 }
 
 impl Marker {
     pub fn from_bytes(bytes : &[u8]) -> Result<Marker, InvalidMarkerError> {
         if bytes.len() != 2 {
-            Err(InvalidMarkerError::new(bytes))
-        } else {
-            match bytes {
-                &[0xffu8, 0xc4u8] => Ok(Marker::DefineHuffmanTable),
-                &[0xffu8, 0xd8u8] => Ok(Marker::StartOfImage),
-                &[0xffu8, 0xd9u8] => Ok(Marker::EndOfImage),
-                &[0xffu8, 0xdau8] => Ok(Marker::StartOfScan),
-                &[0xffu8, 0xdbu8] => Ok(Marker::DefineQuantizationTable),
-                &[0xffu8, 0xfeu8] => Ok(Marker::Comment),
-                _ => Err(InvalidMarkerError::new(bytes)),
-            }
+            return Err(InvalidMarkerError::new(bytes));
+        }
+
+        // Both bytes are known to be present, so these reads can't fail.
+        let mut reader = SliceReader::new(bytes);
+        let first = reader.next_u8().unwrap();
+        let second = reader.next_u8().unwrap();
+
+        if first != 0xffu8 {
+            return Err(InvalidMarkerError::new(bytes));
+        }
+
+        match second {
+            0xc4u8 => Ok(Marker::DefineHuffmanTable),
+            0xd8u8 => Ok(Marker::StartOfImage),
+            0xd9u8 => Ok(Marker::EndOfImage),
+            0xdau8 => Ok(Marker::StartOfScan),
+            0xdbu8 => Ok(Marker::DefineQuantizationTable),
+            0xfeu8 => Ok(Marker::Comment),
+            0xddu8 => Ok(Marker::DefineRestartInterval),
+            second if second >= 0xd0u8 && second <= 0xd7u8 => Ok(Marker::RestartMarker(second - 0xd0u8)),
+            second if second >= 0xe0u8 && second <= 0xefu8 => Ok(Marker::Application(second - 0xe0u8)),
+            second if second >= 0xc0u8 && second <= 0xcfu8 && second != 0xc8u8 && second != 0xccu8 => {
+                let frame_type = match second & 0x0fu8 {
+                    0x0 => FrameType::Baseline,
+                    0x1 | 0x9 => FrameType::ExtendedSequential,
+                    0x2 | 0xa => FrameType::Progressive,
+                    0x3 | 0xb => FrameType::Lossless,
+                    0x5 | 0xd => FrameType::DifferentialSequential,
+                    0x6 | 0xe => FrameType::DifferentialProgressive,
+                    0x7 | 0xf => FrameType::DifferentialLossless,
+                    _ => unreachable!(),
+                };
+                Ok(Marker::StartOfFrame(frame_type))
+            },
+            _ => Err(InvalidMarkerError::new(bytes)),
         }
     }
 }
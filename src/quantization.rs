@@ -0,0 +1,140 @@
+use segment::InvalidSegmentError;
+
+// Maps a zig-zag scan index to its natural (row-major) position in an 8x8
+// block, per the JPEG standard's Figure A.6.
+const ZIG_ZAG : [usize; 64] = [
+     0,  1,  8, 16,  9,  2,  3, 10,
+    17, 24, 32, 25, 18, 11,  4,  5,
+    12, 19, 26, 33, 40, 48, 41, 34,
+    27, 20, 13,  6,  7, 14, 21, 28,
+    35, 42, 49, 56, 57, 50, 43, 36,
+    29, 22, 15, 23, 30, 37, 44, 51,
+    58, 59, 52, 45, 38, 31, 39, 46,
+    53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+#[derive(Debug, PartialEq)]
+pub struct QuantizationTable {
+    pub destination_id : u8,
+    // In natural (row-major) order, not zig-zag order.
+    pub values : [u16; 64],
+}
+
+// A DQT body may pack several tables back-to-back, so this parses until
+// the body is exhausted.
+pub fn parse_tables(body : &[u8]) -> Result<Vec<QuantizationTable>, InvalidSegmentError> {
+    let mut tables = Vec::<QuantizationTable>::new();
+    let mut offset = 0;
+    while offset < body.len() {
+        let (table, consumed) = try!(parse_one_table(&body[offset..]));
+        tables.push(table);
+        offset = offset + consumed;
+    }
+    Ok(tables)
+}
+
+fn parse_one_table(body : &[u8]) -> Result<(QuantizationTable, usize), InvalidSegmentError> {
+    if body.len() < 1 {
+        return Err(InvalidSegmentError::malformed_body(String::from("DQT table is missing its precision/id byte.")));
+    }
+
+    let precision_and_id = body[0];
+    let precision = precision_and_id >> 4;
+    let destination_id = precision_and_id & 0x0f;
+    if destination_id > 3 {
+        return Err(InvalidSegmentError::malformed_body(format!("DQT destination id {} is out of the valid 0-3 range.", destination_id)));
+    }
+
+    let zig_zag_values = match precision {
+        0 => try!(read_8_bit_entries(&body[1..])),
+        1 => try!(read_16_bit_entries(&body[1..])),
+        other => return Err(InvalidSegmentError::malformed_body(format!("DQT precision nibble {} is neither 0 (8-bit) nor 1 (16-bit).", other))),
+    };
+
+    let mut values = [0u16; 64];
+    for zig_zag_index in 0..64 {
+        values[ZIG_ZAG[zig_zag_index]] = zig_zag_values[zig_zag_index];
+    }
+
+    let consumed = 1 + if precision == 0 { 64 } else { 128 };
+    Ok((QuantizationTable { destination_id : destination_id, values : values }, consumed))
+}
+
+fn read_8_bit_entries(bytes : &[u8]) -> Result<[u16; 64], InvalidSegmentError> {
+    if bytes.len() < 64 {
+        return Err(InvalidSegmentError::malformed_body(format!("DQT table claims 64 8-bit entries but only {} bytes remain.", bytes.len())));
+    }
+    let mut entries = [0u16; 64];
+    for i in 0..64 {
+        entries[i] = bytes[i] as u16;
+    }
+    Ok(entries)
+}
+
+fn read_16_bit_entries(bytes : &[u8]) -> Result<[u16; 64], InvalidSegmentError> {
+    if bytes.len() < 128 {
+        return Err(InvalidSegmentError::malformed_body(format!("DQT table claims 64 16-bit entries but only {} bytes remain.", bytes.len())));
+    }
+    let mut entries = [0u16; 64];
+    for i in 0..64 {
+        entries[i] = (bytes[2*i] as u16) * 256 + (bytes[2*i + 1] as u16);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod quantization_tests {
+    use super::*;
+
+    #[test]
+    fn parses_8_bit_table_and_dezigzags() {
+        let mut body = vec![0x00u8]; // precision 0, destination 0
+        for i in 0..64u8 {
+            body.push(i);
+        }
+        let tables = parse_tables(&body).unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].destination_id, 0);
+        // Zig-zag index 0 is always natural index 0 (DC coefficient).
+        assert_eq!(tables[0].values[0], 0);
+        // Zig-zag index 1 (value 1) lands at natural index 1.
+        assert_eq!(tables[0].values[1], 1);
+        // Zig-zag index 2 (value 2) lands at natural index 8.
+        assert_eq!(tables[0].values[8], 2);
+    }
+
+    #[test]
+    fn parses_16_bit_table() {
+        let mut body = vec![0x13u8]; // precision 1, destination 3
+        for i in 0..64u16 {
+            body.push((i / 256) as u8);
+            body.push((i % 256) as u8);
+        }
+        let tables = parse_tables(&body).unwrap();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].destination_id, 3);
+        assert_eq!(tables[0].values[0], 0);
+        assert_eq!(tables[0].values[8], 2);
+    }
+
+    #[test]
+    fn rejects_out_of_range_destination_id() {
+        let mut body = vec![0x04u8]; // precision 0, destination 4 (invalid)
+        for i in 0..64u8 {
+            body.push(i);
+        }
+        let result = parse_tables(&body);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_body() {
+        let body = vec![0x00u8, 0x01u8, 0x02u8]; // precision 0, but only 2 of 64 entries
+        let result = parse_tables(&body);
+
+        assert!(result.is_err());
+    }
+}
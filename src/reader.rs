@@ -0,0 +1,124 @@
+// A small cursor abstraction over a byte source. Parsing code built on
+// top of this stops doing its own `bytes[n]`/`bytes[n]*256 + bytes[n+1]`
+// arithmetic and bounds checks - `marker::Marker::from_bytes` and
+// `segment::locate_segment` both read their fields through this trait's
+// methods. Both still take a fully buffered `&[u8]` and wrap it in a
+// `SliceReader` themselves.
+#[derive(Debug, PartialEq)]
+pub enum ReadError {
+    ExhaustedInput,
+}
+
+pub trait Reader {
+    fn next_u8(&mut self) -> Result<u8, ReadError>;
+    fn next_u16_be(&mut self) -> Result<u16, ReadError>;
+    fn next_n(&mut self, buf : &mut [u8]) -> Result<(), ReadError>;
+
+    // A bookmark that can be compared against a later `offset()` to find
+    // out how many bytes were consumed in between.
+    fn mark(&self) -> usize;
+    fn offset(&self) -> usize;
+}
+
+// The only `Reader` implementation: a cursor over an in-memory slice.
+pub struct SliceReader<'a> {
+    data : &'a [u8],
+    offset : usize,
+}
+
+impl<'a> SliceReader<'a> {
+    pub fn new(data : &'a [u8]) -> SliceReader<'a> {
+        SliceReader { data : data, offset : 0 }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.offset
+    }
+
+    // The bytes consumed since `mark`.
+    pub fn slice_from(&self, mark : usize) -> &'a [u8] {
+        &self.data[mark .. self.offset]
+    }
+}
+
+impl<'a> Reader for SliceReader<'a> {
+    fn next_u8(&mut self) -> Result<u8, ReadError> {
+        if self.offset >= self.data.len() {
+            return Err(ReadError::ExhaustedInput);
+        }
+        let byte = self.data[self.offset];
+        self.offset = self.offset + 1;
+        Ok(byte)
+    }
+
+    fn next_u16_be(&mut self) -> Result<u16, ReadError> {
+        let high = try!(self.next_u8());
+        let low = try!(self.next_u8());
+        Ok((high as u16) * 256 + (low as u16))
+    }
+
+    fn next_n(&mut self, buf : &mut [u8]) -> Result<(), ReadError> {
+        if self.remaining() < buf.len() {
+            return Err(ReadError::ExhaustedInput);
+        }
+        for i in 0..buf.len() {
+            buf[i] = self.data[self.offset + i];
+        }
+        self.offset = self.offset + buf.len();
+        Ok(())
+    }
+
+    fn mark(&self) -> usize {
+        self.offset
+    }
+
+    fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[cfg(test)]
+mod reader_tests {
+    use super::*;
+
+    #[test]
+    fn reads_u8_and_u16_be() {
+        let bytes = vec![0x01u8, 0x00u8, 0x02u8];
+        let mut reader = SliceReader::new(&bytes);
+
+        assert_eq!(reader.next_u8(), Ok(0x01u8));
+        assert_eq!(reader.next_u16_be(), Ok(2u16));
+        assert_eq!(reader.offset(), 3);
+    }
+
+    #[test]
+    fn next_n_copies_into_buffer() {
+        let bytes = vec![0xabu8, 0xcdu8, 0xefu8];
+        let mut reader = SliceReader::new(&bytes);
+        let mut buf = [0u8; 2];
+
+        assert_eq!(reader.next_n(&mut buf), Ok(()));
+        assert_eq!(buf, [0xabu8, 0xcdu8]);
+        assert_eq!(reader.offset(), 2);
+    }
+
+    #[test]
+    fn reports_exhausted_input() {
+        let bytes = vec![0x01u8];
+        let mut reader = SliceReader::new(&bytes);
+
+        assert_eq!(reader.next_u16_be(), Err(ReadError::ExhaustedInput));
+    }
+
+    #[test]
+    fn mark_and_slice_from_track_consumed_bytes() {
+        let bytes = vec![0xabu8, 0xcdu8, 0xefu8, 0x01u8];
+        let mut reader = SliceReader::new(&bytes);
+        let mark = reader.mark();
+
+        reader.next_u8().unwrap();
+        reader.next_u8().unwrap();
+
+        assert_eq!(reader.slice_from(mark), &[0xabu8, 0xcdu8][..]);
+    }
+}
@@ -1,4 +1,5 @@
 use marker::*;
+use reader::{Reader, SliceReader};
 use std::error::Error;
 use std::fmt;
 
@@ -6,67 +7,238 @@ use std::fmt;
 pub struct Segment {
     marker : Marker,
     data : Option<Vec<u8>>,
+    // Only populated for `StartOfScan`: the entropy-coded bytes that follow
+    // the (length-prefixed) scan header, up to but not including the next
+    // real marker. Byte-stuffed 0xFF 0x00 sequences and RSTn markers inside
+    // this range are left as-is, not decoded.
+    scan_data : Option<Vec<u8>>,
 }
 
-impl Segment {
-    pub fn read_from_start_of_bytes(bytes : &[u8]) -> Result<Segment, InvalidSegmentError> {
-        let bytes_len = bytes.len();
-        if bytes_len < 2 {
-            Err(InvalidSegmentError::too_few_bytes(bytes_len))
-        } else {
-            let marker = Marker::from_bytes(&bytes[0..2]);
-            match marker {
-                Err(error) => Err(InvalidSegmentError::invalid_marker(error)),
-                Ok(marker) => {
-                    if marker == Marker::StartOfImage || marker == Marker::EndOfImage {
-                        // Easy case - these markers have no length or data
-                        Ok(Segment { marker : marker, data : None })
-                    } else {
-                        if bytes_len < 4 {
-                            Err(InvalidSegmentError::no_length_bytes())
-                        } else {
-                            let length = (bytes[2] as usize)*256 + (bytes[3] as usize);
-                            if length < 2 {
-                                Err(InvalidSegmentError::length_less_than_two(length))
-                            } else if bytes_len < length + 2 {
-                                Err(InvalidSegmentError::too_few_data_bytes(length - 2, bytes_len - 4))
-                            } else {
-                                Ok(Segment { marker : marker, data : Some(bytes[4..4+length-2].to_vec())})
-                            }
-                        }
-                    }
-                }
+// A short read (the buffer just doesn't have the next segment's bytes
+// yet) is a different outcome from a corrupt one (the bytes present are
+// not a valid segment), so this is richer than a plain `Result`.
+#[derive(Debug)]
+pub enum ReadOutcome {
+    Complete(Segment, usize),
+    Incomplete(usize),
+    Invalid(InvalidSegmentError),
+}
+
+#[derive(Debug)]
+pub enum ParseOutcome {
+    Complete(Vec<Segment>),
+    Incomplete(Vec<Segment>, usize),
+    Invalid(ParseToSegmentsError),
+}
+
+// A borrowed view of a segment, sliced directly out of the input with no
+// copying. Produced by `SegmentIterator` for callers that only want to
+// walk the structure (e.g. to find a particular marker) and don't need
+// to own megabytes of scan data.
+#[derive(Debug, PartialEq)]
+pub struct SegmentRef<'a> {
+    pub marker : Marker,
+    pub data : Option<&'a [u8]>,
+    pub scan_data : Option<&'a [u8]>,
+}
+
+// Where a segment's marker, header data and (for StartOfScan) entropy-coded
+// scan data sit within a byte slice, without copying anything out. Shared
+// by `read_from_start_of_bytes` (which then copies into an owned `Segment`)
+// and `SegmentIterator` (which slices the caller's borrow directly).
+struct SegmentLayout {
+    marker : Marker,
+    header_data : Option<(usize, usize)>,
+    scan_data : Option<(usize, usize)>,
+    consumed : usize,
+}
+
+enum LocateOutcome {
+    Complete(SegmentLayout),
+    Incomplete(usize),
+    Invalid(InvalidSegmentError),
+}
+
+fn locate_segment(bytes : &[u8]) -> LocateOutcome {
+    let bytes_len = bytes.len();
+    if bytes_len < 2 {
+        return LocateOutcome::Incomplete(2 - bytes_len);
+    }
+
+    let mut reader = SliceReader::new(bytes);
+    let mark = reader.mark();
+    let marker_bytes = [reader.next_u8().unwrap(), reader.next_u8().unwrap()];
+    let marker = match Marker::from_bytes(&marker_bytes) {
+        Err(error) => return LocateOutcome::Invalid(InvalidSegmentError::invalid_marker(error)),
+        Ok(marker) => marker,
+    };
+    if marker == Marker::StartOfImage || marker == Marker::EndOfImage {
+        // Easy case - these markers have no length or data
+        return LocateOutcome::Complete(SegmentLayout { marker : marker, header_data : None, scan_data : None, consumed : reader.offset() - mark });
+    }
+    if bytes_len < 4 {
+        return LocateOutcome::Incomplete(4 - bytes_len);
+    }
+    let length = reader.next_u16_be().unwrap() as usize;
+    if length < 2 {
+        return LocateOutcome::Invalid(InvalidSegmentError::length_less_than_two(length));
+    }
+    if bytes_len < length + 2 {
+        return LocateOutcome::Incomplete((length + 2) - bytes_len);
+    }
+    let header_end = reader.offset() + (length - 2);
+    let header_data = Some((reader.offset(), header_end));
+    if marker != Marker::StartOfScan {
+        return LocateOutcome::Complete(SegmentLayout { marker : marker, header_data : header_data, scan_data : None, consumed : length + 2 });
+    }
+    // StartOfScan's header is length-prefixed as usual, but it's followed by
+    // an unbounded stream of entropy-coded bytes that is NOT length-prefixed -
+    // it runs until the next real marker.
+    match find_end_of_scan_data(&bytes[header_end..]) {
+        None => LocateOutcome::Incomplete(1),
+        Some(scan_length) => {
+            let scan_data = Some((header_end, header_end + scan_length));
+            LocateOutcome::Complete(SegmentLayout { marker : marker, header_data : header_data, scan_data : scan_data, consumed : header_end + scan_length })
+        }
+    }
+}
+
+// Scans entropy-coded scan data for the first byte `0xFF` that is followed
+// by something other than a byte-stuffed `0x00` or a RSTn restart marker
+// byte (0xD0-0xD7), both of which are part of the scan rather than the end
+// of it. Returns the number of scan bytes before that real marker, or None
+// if the end of the supplied slice is reached first (need more bytes).
+fn find_end_of_scan_data(bytes : &[u8]) -> Option<usize> {
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == 0xff {
+            if i + 1 >= bytes.len() {
+                return None;
             }
+            let next = bytes[i + 1];
+            if next == 0x00 || (next >= 0xd0 && next <= 0xd7) {
+                i = i + 2;
+            } else {
+                return Some(i);
+            }
+        } else {
+            i = i + 1;
+        }
+    }
+    None
+}
+
+// Walks a borrowed byte slice, yielding `SegmentRef`s that point straight
+// into the input rather than materializing a `Vec<Segment>` up front.
+pub struct SegmentIterator<'a> {
+    data : &'a [u8],
+    offset : usize,
+    stopped : bool,
+    incomplete_needed : Option<usize>,
+}
+
+impl<'a> SegmentIterator<'a> {
+    pub fn new(data : &'a [u8]) -> SegmentIterator<'a> {
+        SegmentIterator { data : data, offset : 0, stopped : false, incomplete_needed : None }
+    }
+
+    // How many bytes have been consumed by the segments yielded so far.
+    // Once an `Err` item has been yielded, this is the offset at which
+    // iteration stopped.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    // Set once the most recently yielded `Err` was caused by truncated
+    // input rather than a genuinely invalid segment, with the number of
+    // further bytes needed to make progress. `Item`'s plain
+    // `Result<_, InvalidSegmentError>` can't otherwise tell the two apart.
+    pub fn incomplete_needed(&self) -> Option<usize> {
+        self.incomplete_needed
+    }
+}
+
+impl<'a> Iterator for SegmentIterator<'a> {
+    type Item = Result<SegmentRef<'a>, InvalidSegmentError>;
+
+    fn next(&mut self) -> Option<Result<SegmentRef<'a>, InvalidSegmentError>> {
+        if self.stopped || self.offset >= self.data.len() {
+            return None;
+        }
+        match locate_segment(&self.data[self.offset..]) {
+            LocateOutcome::Complete(layout) => {
+                let header_data = layout.header_data.map(|(start, end)| &self.data[self.offset + start .. self.offset + end]);
+                let scan_data = layout.scan_data.map(|(start, end)| &self.data[self.offset + start .. self.offset + end]);
+                self.offset = self.offset + layout.consumed;
+                Some(Ok(SegmentRef { marker : layout.marker, data : header_data, scan_data : scan_data }))
+            },
+            LocateOutcome::Incomplete(needed) => {
+                self.incomplete_needed = Some(needed);
+                self.stopped = true;
+                Some(Err(InvalidSegmentError::truncated(needed)))
+            },
+            LocateOutcome::Invalid(error) => {
+                self.stopped = true;
+                Some(Err(error))
+            },
         }
-            
     }
+}
 
-    pub fn parse_bytes_to_segments(bytes : &[u8]) -> Result<Vec<Segment>, ParseToSegmentsError> {
+impl Segment {
+    // Copies a borrowed `SegmentRef` into an owned `Segment`.
+    fn from_ref(segment_ref : SegmentRef) -> Segment {
+        Segment {
+            marker : segment_ref.marker,
+            data : segment_ref.data.map(|data| data.to_vec()),
+            scan_data : segment_ref.scan_data.map(|data| data.to_vec()),
+        }
+    }
+
+    // Reads (at most) one segment from the start of `bytes`. Unlike a plain
+    // Result, this distinguishes "not enough bytes yet" from "this is not
+    // a valid segment" - a truncated buffer from a socket or chunked file
+    // read isn't corrupt, it just needs more data appended before retrying.
+    pub fn read_from_start_of_bytes(bytes : &[u8]) -> ReadOutcome {
+        match locate_segment(bytes) {
+            LocateOutcome::Complete(layout) => {
+                let data = layout.header_data.map(|(start, end)| bytes[start..end].to_vec());
+                let scan_data = layout.scan_data.map(|(start, end)| bytes[start..end].to_vec());
+                ReadOutcome::Complete(Segment { marker : layout.marker, data : data, scan_data : scan_data }, layout.consumed)
+            },
+            LocateOutcome::Incomplete(needed) => ReadOutcome::Incomplete(needed),
+            LocateOutcome::Invalid(error) => ReadOutcome::Invalid(error),
+        }
+    }
+
+    // Walks `bytes` pulling out as many segments as are fully present. Stops
+    // and reports `Incomplete` (with the segments found so far, and how many
+    // more bytes are needed to make progress) rather than failing outright,
+    // so a caller reading from a stream can append freshly-read bytes and
+    // call this again.
+    //
+    // A thin wrapper around `SegmentIterator` - the two must never walk the
+    // input differently, so this doesn't re-derive marker/length/scan-data
+    // boundaries itself.
+    pub fn parse_bytes_to_segments(bytes : &[u8]) -> ParseOutcome {
+        let mut iter = SegmentIterator::new(bytes);
         let mut parsed_segments = Vec::<Segment>::new();
-        let mut bytes_parsed = 0;
-        let mut segments_parsed = 0;
-        let mut first_error = None;
-        let bytes_len = bytes.len();
-        while bytes_parsed < bytes.len() && first_error.is_none() {
-            match Segment::read_from_start_of_bytes(&bytes[bytes_parsed..]) {
-                Ok(segment) => {
-                    let segment_length = match segment.data {
-                        Some(ref data) => data.len() + 4,
-                        None => 2,
+        while let Some(item) = iter.next() {
+            match item {
+                Ok(segment_ref) => parsed_segments.push(Segment::from_ref(segment_ref)),
+                Err(error) => {
+                    return match iter.incomplete_needed() {
+                        Some(needed) => ParseOutcome::Incomplete(parsed_segments, needed),
+                        None => {
+                            let segments_parsed = parsed_segments.len() as u32;
+                            let bytes_parsed = iter.offset();
+                            ParseOutcome::Invalid(ParseToSegmentsError::new(bytes_parsed, segments_parsed, box(error)))
+                        },
                     };
-                    parsed_segments.push(segment);   
-                    bytes_parsed = bytes_parsed + segment_length;
-                    segments_parsed = segments_parsed + 1;
                 },
-                Err(error) => {
-                    first_error = Some(ParseToSegmentsError::new(bytes_parsed, segments_parsed, box(error)));
-                }
             }
         }
-        match first_error {
-            None => Ok(parsed_segments),
-            Some(error) => Err(error),
-        }
+        ParseOutcome::Complete(parsed_segments)
     }
 
     pub fn summary_string(&self) -> String {
@@ -75,7 +247,10 @@ impl Segment {
             None => String::from("None"),
             Some(ref data) => format!("{} bytes", data.len()),
         };
-        format!("{{ marker : {}, data : {} }}", marker_string, data_sting)
+        match self.scan_data {
+            None => format!("{{ marker : {}, data : {} }}", marker_string, data_sting),
+            Some(ref scan_data) => format!("{{ marker : {}, data : {}, scan_data : {} bytes }}", marker_string, data_sting, scan_data.len()),
+        }
     }
 }
 
@@ -86,28 +261,22 @@ pub struct InvalidSegmentError {
 }
 
 impl InvalidSegmentError {
-    fn too_few_bytes(n : usize) -> InvalidSegmentError {
-        if n == 0 {
-            InvalidSegmentError { message : String::from("Attempted to read segment from an empty byte slice."), underlying_error : None }
-        } else {
-            InvalidSegmentError { message : String::from(format!("Attempted to read segment from a slice containing only {} bytes.", n)), underlying_error : None }
-        }
-    }
-
     fn invalid_marker(error : InvalidMarkerError) -> InvalidSegmentError {
         InvalidSegmentError { message : String::from("Segment began with an invalid marker."), underlying_error : Some(box(error)) }
     }
 
-    fn no_length_bytes() -> InvalidSegmentError {
-        InvalidSegmentError { message : String::from("Marker requires length bytes, but have fewer than two byes left in the input."), underlying_error : None }
-    }
-
     fn length_less_than_two(n : usize) -> InvalidSegmentError {
         InvalidSegmentError { message : String::from(format!("Length of segment, {}, was less than two. This doesn't even cover the two data bytes!", n)), underlying_error : None }
     }
 
-    fn too_few_data_bytes(n_expected : usize, n_actual : usize) -> InvalidSegmentError {
-        InvalidSegmentError {message : String::from(format!("Segment wants {} data bytes, but there are only {} bytes remaining in the slice.", n_expected, n_actual)), underlying_error : None }
+    fn truncated(needed : usize) -> InvalidSegmentError {
+        InvalidSegmentError { message : String::from(format!("Segment is truncated - {} more bytes are needed to complete it.", needed)), underlying_error : None }
+    }
+
+    // For use by segment-body parsers (DHT, DQT, SOFn, ...) that need to
+    // report a malformed body in terms specific to their own format.
+    pub fn malformed_body(message : String) -> InvalidSegmentError {
+        InvalidSegmentError { message : message, underlying_error : None }
     }
 }
 
@@ -170,19 +339,19 @@ mod segment_tests {
     fn too_few_bytes() {
         let bytes : Vec<u8> = vec![];
         let result = Segment::read_from_start_of_bytes(&bytes[..]);
-        let expected_err = InvalidSegmentError::too_few_bytes(0);
-
-        assert!(result.is_err());
 
-        assert_eq!(result.unwrap_err().message, expected_err.message);
+        match result {
+            ReadOutcome::Incomplete(needed) => assert_eq!(needed, 2),
+            _ => panic!("Empty slice should report Incomplete."),
+        }
 
         let bytes1 : Vec<u8> = vec![0u8];
         let result1 = Segment::read_from_start_of_bytes(&bytes1[..]);
-        let expected_err1 = InvalidSegmentError::too_few_bytes(1);
 
-        assert!(result1.is_err());
-
-        assert_eq!(result1.unwrap_err().message, expected_err1.message);
+        match result1 {
+            ReadOutcome::Incomplete(needed) => assert_eq!(needed, 1),
+            _ => panic!("One-byte slice should report Incomplete."),
+        }
     }
 
     #[test]
@@ -193,27 +362,32 @@ mod segment_tests {
         let expected_inner_description = String::from(expected_inner_error.description());
         let expected_err = InvalidSegmentError::invalid_marker(expected_inner_error);
 
-        assert!(result.is_err());
-
-        assert_eq!(result.as_ref().unwrap_err().message, expected_err.message);
-        assert_eq!((*result.unwrap_err().underlying_error.unwrap()).description(), expected_inner_description);
+        match result {
+            ReadOutcome::Invalid(error) => {
+                assert_eq!(error.message, expected_err.message);
+                assert_eq!((*error.underlying_error.unwrap()).description(), expected_inner_description);
+            },
+            _ => panic!("Invalid marker bytes should report Invalid."),
+        }
     }
 
     #[test]
     fn no_length_bytes() {
         let bytes = vec![0xffu8, 0xfeu8];   // Comment marker, should have length bytes
         let result = Segment::read_from_start_of_bytes(&bytes[..]);
-        let expected_err = InvalidSegmentError::no_length_bytes();
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().message, expected_err.message);
+        match result {
+            ReadOutcome::Incomplete(needed) => assert_eq!(needed, 2),
+            _ => panic!("Marker with no length bytes should report Incomplete."),
+        }
 
         let bytes1 = vec![0xffu8, 0xfeu8, 0x01u8];   // Comment marker, should have length bytes
         let result1 = Segment::read_from_start_of_bytes(&bytes1[..]);
-        let expected_err1 = InvalidSegmentError::no_length_bytes();
 
-        assert!(result1.is_err());
-        assert_eq!(result1.unwrap_err().message, expected_err1.message);
+        match result1 {
+            ReadOutcome::Incomplete(needed) => assert_eq!(needed, 1),
+            _ => panic!("Marker with one length byte should report Incomplete."),
+        }
     }
 
 
@@ -223,18 +397,21 @@ mod segment_tests {
         let result = Segment::read_from_start_of_bytes(&bytes[..]);
         let expected_err = InvalidSegmentError::length_less_than_two(1);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().message, expected_err.message);
+        match result {
+            ReadOutcome::Invalid(error) => assert_eq!(error.message, expected_err.message),
+            _ => panic!("Length of one should be reported as Invalid, not Incomplete."),
+        }
     }
 
     #[test]
     fn too_few_data_bytes() {
         let bytes = vec![0xffu8, 0xfeu8, 0x00u8, 0x06u8, 0xabu8, 0xcdu8, 0xefu8];   // Comment marker, with not enough data
         let result = Segment::read_from_start_of_bytes(&bytes[..]);
-        let expected_err = InvalidSegmentError::too_few_data_bytes(4, 3);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().message, expected_err.message);
+        match result {
+            ReadOutcome::Incomplete(needed) => assert_eq!(needed, 1),
+            _ => panic!("Truncated data should report Incomplete."),
+        }
     }
 
 
@@ -242,20 +419,30 @@ mod segment_tests {
     fn marker_no_requiring_data() {
         let bytes = vec![0xffu8, 0xd8u8, 0x00u8, 0x06u8, 0xabu8, 0xcdu8, 0xefu8];   // StartOfImage, plus padding
         let result = Segment::read_from_start_of_bytes(&bytes[..]);
-        let expected_ok = Segment { marker : Marker::StartOfImage, data : None };
-
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), expected_ok);
+        let expected_ok = Segment { marker : Marker::StartOfImage, data : None, scan_data : None };
+
+        match result {
+            ReadOutcome::Complete(segment, consumed) => {
+                assert_eq!(segment, expected_ok);
+                assert_eq!(consumed, 2);
+            },
+            _ => panic!("StartOfImage should report Complete."),
+        }
     }
 
     #[test]
     fn marker_with_data() {
         let bytes = vec![0xffu8, 0xfeu8, 0x00u8, 0x06u8, 0xabu8, 0xcdu8, 0xefu8, 0x03u8];   // Comment marker, with data
         let result = Segment::read_from_start_of_bytes(&bytes[..]);
-        let expected_ok = Segment { marker : Marker::Comment, data : Some(vec![0xabu8, 0xcdu8, 0xefu8, 0x03u8]) };
-
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), expected_ok);
+        let expected_ok = Segment { marker : Marker::Comment, data : Some(vec![0xabu8, 0xcdu8, 0xefu8, 0x03u8]), scan_data : None };
+
+        match result {
+            ReadOutcome::Complete(segment, consumed) => {
+                assert_eq!(segment, expected_ok);
+                assert_eq!(consumed, 8);
+            },
+            _ => panic!("Comment marker with enough data should report Complete."),
+        }
     }
 
 
@@ -263,32 +450,108 @@ mod segment_tests {
     fn marker_with_data_and_padding() {
         let bytes = vec![0xffu8, 0xfeu8, 0x00u8, 0x06u8, 0xabu8, 0xcdu8, 0xefu8, 0x03u8, 0x00u8, 0x17u8];   // Comment marker, with data
         let result = Segment::read_from_start_of_bytes(&bytes[..]);
-        let expected_ok = Segment { marker : Marker::Comment, data : Some(vec![0xabu8, 0xcdu8, 0xefu8, 0x03u8]) };
-
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), expected_ok);
+        let expected_ok = Segment { marker : Marker::Comment, data : Some(vec![0xabu8, 0xcdu8, 0xefu8, 0x03u8]), scan_data : None };
+
+        match result {
+            ReadOutcome::Complete(segment, consumed) => {
+                assert_eq!(segment, expected_ok);
+                assert_eq!(consumed, 8);
+            },
+            _ => panic!("Comment marker with trailing padding should report Complete, ignoring the padding."),
+        }
     }
 
     #[test]
     fn parse_valid_segments() {
         let bytes = vec![0xffu8, 0xd8u8, 0xffu8, 0xfeu8, 0x00u8, 0x05u8, 0x01u8, 0x23u8, 0x45u8];
         let result = Segment::parse_bytes_to_segments(&bytes);
-        let expected_segments = vec![Segment { marker : Marker::StartOfImage, data : None },
-                                     Segment { marker : Marker::Comment, data : Some(vec![0x01u8, 0x23u8, 0x45u8]) }];
+        let expected_segments = vec![Segment { marker : Marker::StartOfImage, data : None, scan_data : None },
+                                     Segment { marker : Marker::Comment, data : Some(vec![0x01u8, 0x23u8, 0x45u8]), scan_data : None }];
 
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), expected_segments);
+        match result {
+            ParseOutcome::Complete(segments) => assert_eq!(segments, expected_segments),
+            _ => panic!("Fully present segments should report Complete."),
+        }
+    }
 
+    #[test]
+    fn parse_incomplete_segments() {
+        let bytes = vec![0xffu8, 0xd8u8, 0xffu8, 0xfeu8, 0x00u8, 0x05u8, 0x01u8, 0x23u8];
+        let result = Segment::parse_bytes_to_segments(&bytes);
+        let expected_segments = vec![Segment { marker : Marker::StartOfImage, data : None, scan_data : None }];
+
+        match result {
+            ParseOutcome::Incomplete(segments, needed) => {
+                assert_eq!(segments, expected_segments);
+                assert_eq!(needed, 1);
+            },
+            _ => panic!("Truncated trailing segment should report Incomplete."),
+        }
     }
 
     #[test]
     fn parse_invalid_segments() {
-        let bytes = vec![0xffu8, 0xd8u8, 0xffu8, 0xfeu8, 0x00u8, 0x05u8, 0x01u8, 0x23u8];
+        let bytes = vec![0xffu8, 0xd8u8, 0xffu8, 0xfeu8, 0x00u8, 0x01u8];
         let result = Segment::parse_bytes_to_segments(&bytes);
-        let expected_error = ParseToSegmentsError::new(2, 1, box(InvalidSegmentError::too_few_data_bytes(3, 2)));
+        let expected_error = ParseToSegmentsError::new(2, 1, box(InvalidSegmentError::length_less_than_two(1)));
+
+        match result {
+            ParseOutcome::Invalid(error) => assert_eq!(error.description(), expected_error.description()),
+            _ => panic!("Corrupt segment should report Invalid."),
+        }
+    }
+
+    #[test]
+    fn iterate_valid_segments() {
+        let bytes = vec![0xffu8, 0xd8u8, 0xffu8, 0xfeu8, 0x00u8, 0x05u8, 0x01u8, 0x23u8, 0x45u8];
+        let segments : Vec<_> = SegmentIterator::new(&bytes).collect();
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].as_ref().unwrap(), &SegmentRef { marker : Marker::StartOfImage, data : None, scan_data : None });
+        assert_eq!(segments[1].as_ref().unwrap(), &SegmentRef { marker : Marker::Comment, data : Some(&[0x01u8, 0x23u8, 0x45u8][..]), scan_data : None });
+    }
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().description(), expected_error.description());
+    #[test]
+    fn iterate_invalid_segment_stops_iteration() {
+        let bytes = vec![0xffu8, 0xd8u8, 0xffu8, 0xfeu8, 0x00u8, 0x01u8, 0xffu8, 0xd8u8];
+        let segments : Vec<_> = SegmentIterator::new(&bytes).collect();
 
+        assert_eq!(segments.len(), 2);
+        assert!(segments[0].is_ok());
+        assert!(segments[1].is_err());
+    }
+
+    #[test]
+    fn start_of_scan_captures_entropy_coded_data() {
+        // SOS header (length 4, so 2 header bytes), followed by entropy-coded
+        // data containing a byte-stuffed 0xFF and a restart marker, ending at
+        // the next real marker (EndOfImage).
+        let bytes = vec![0xffu8, 0xdau8, 0x00u8, 0x04u8, 0x01u8, 0x00u8,
+                          0xabu8, 0xffu8, 0x00u8, 0xcdu8, 0xffu8, 0xd0u8, 0xefu8,
+                          0xffu8, 0xd9u8];
+        let result = Segment::read_from_start_of_bytes(&bytes);
+        let expected_header = vec![0x01u8, 0x00u8];
+        let expected_scan_data = vec![0xabu8, 0xffu8, 0x00u8, 0xcdu8, 0xffu8, 0xd0u8, 0xefu8];
+
+        match result {
+            ReadOutcome::Complete(segment, consumed) => {
+                assert_eq!(segment.marker, Marker::StartOfScan);
+                assert_eq!(segment.data, Some(expected_header));
+                assert_eq!(segment.scan_data, Some(expected_scan_data));
+                assert_eq!(consumed, 13);
+            },
+            _ => panic!("StartOfScan with a following real marker should report Complete."),
+        }
+    }
+
+    #[test]
+    fn start_of_scan_without_terminating_marker_is_incomplete() {
+        let bytes = vec![0xffu8, 0xdau8, 0x00u8, 0x04u8, 0x01u8, 0x00u8, 0xabu8, 0xcdu8];
+        let result = Segment::read_from_start_of_bytes(&bytes);
+
+        match result {
+            ReadOutcome::Incomplete(_) => (),
+            _ => panic!("StartOfScan with no terminating marker yet should report Incomplete."),
+        }
     }
 }